@@ -0,0 +1,179 @@
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+
+use std::time::Duration;
+
+use crate::config::{CompiledSite, Site};
+use crate::logger::Logger;
+use crate::monitor::monitor_site;
+use crate::notify::Notifier;
+use crate::registry::{Registry, SiteEntry};
+
+#[derive(Clone)]
+struct ApiState {
+    registry: Registry,
+    logger: Logger,
+    client: reqwest::Client,
+    notifier: Notifier,
+    notify_cooldown: Duration,
+}
+
+#[derive(Serialize)]
+struct SiteView {
+    url: String,
+    interval: u64,
+    ignore_patterns: Vec<String>,
+    watch_selector: Option<String>,
+    timeout_ms: Option<u64>,
+    retries: Option<u32>,
+    retry_backoff_ms: Option<u64>,
+    headers: std::collections::HashMap<String, String>,
+    is_up: bool,
+    last_hash: Option<String>,
+    last_size: Option<usize>,
+    last_load_time: Option<u128>,
+}
+
+/// `Site.url` is a full URL and so contains literal `/`s that a `:url` path
+/// segment can't match without percent-encoding every one of them. Take it
+/// as a query parameter instead.
+#[derive(Deserialize)]
+struct UrlQuery {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct NewSite {
+    url: String,
+    interval: u64,
+    #[serde(default)]
+    ignore_patterns: Vec<String>,
+    #[serde(default)]
+    watch_selector: Option<String>,
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+    #[serde(default)]
+    retries: Option<u32>,
+    #[serde(default)]
+    retry_backoff_ms: Option<u64>,
+    #[serde(default)]
+    headers: std::collections::HashMap<String, String>,
+}
+
+/// Runs the embedded control/status API until the process is killed. Spawned
+/// as its own task from `main` when `--api-addr` is set; the monitoring
+/// tasks keep running independently of whether anyone is listening.
+pub async fn serve(
+    addr: String,
+    registry: Registry,
+    logger: Logger,
+    client: reqwest::Client,
+    notifier: Notifier,
+    notify_cooldown: Duration,
+) -> std::io::Result<()> {
+    let state = ApiState {
+        registry,
+        logger,
+        client,
+        notifier,
+        notify_cooldown,
+    };
+
+    let app = Router::new()
+        .route("/sites", get(list_sites).post(add_site).delete(remove_site))
+        .route("/sites/history", get(site_history))
+        .with_state(state);
+
+    let listener = TcpListener::bind(&addr).await?;
+    axum::serve(listener, app).await
+}
+
+async fn list_sites(State(state): State<ApiState>) -> Json<Vec<SiteView>> {
+    let registry = state.registry.lock().unwrap();
+    let views = registry
+        .values()
+        .map(|entry| SiteView {
+            url: entry.site.site.url.clone(),
+            interval: entry.site.site.interval,
+            ignore_patterns: entry.site.site.ignore_patterns.clone(),
+            watch_selector: entry.site.site.watch_selector.clone(),
+            timeout_ms: entry.site.site.timeout_ms,
+            retries: entry.site.site.retries,
+            retry_backoff_ms: entry.site.site.retry_backoff_ms,
+            headers: entry.site.site.headers.clone(),
+            is_up: entry.state.is_up,
+            last_hash: entry.state.last_hash.clone(),
+            last_size: entry.state.last_size,
+            last_load_time: entry.state.last_load_time,
+        })
+        .collect();
+    Json(views)
+}
+
+async fn site_history(
+    State(state): State<ApiState>,
+    Query(query): Query<UrlQuery>,
+) -> Result<Json<Vec<crate::config::CheckRecord>>, StatusCode> {
+    let registry = state.registry.lock().unwrap();
+    match registry.get(&query.url) {
+        Some(entry) => Ok(Json(entry.state.history.clone())),
+        None => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+async fn add_site(
+    State(state): State<ApiState>,
+    Json(new_site): Json<NewSite>,
+) -> Result<StatusCode, StatusCode> {
+    let mut registry = state.registry.lock().unwrap();
+    if registry.contains_key(&new_site.url) {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let site = CompiledSite::compile(Site {
+        url: new_site.url.clone(),
+        interval: new_site.interval,
+        ignore_patterns: new_site.ignore_patterns,
+        watch_selector: new_site.watch_selector,
+        timeout_ms: new_site.timeout_ms,
+        retries: new_site.retries,
+        retry_backoff_ms: new_site.retry_backoff_ms,
+        headers: new_site.headers,
+    })
+    .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let handle = tokio::spawn(monitor_site(
+        site.clone(),
+        state.registry.clone(),
+        state.logger.clone(),
+        state.client.clone(),
+        state.notifier.clone(),
+        state.notify_cooldown,
+    ));
+
+    registry.insert(
+        site.site.url.clone(),
+        SiteEntry {
+            site,
+            state: crate::config::SiteState::new(),
+            handle,
+        },
+    );
+
+    Ok(StatusCode::CREATED)
+}
+
+async fn remove_site(State(state): State<ApiState>, Query(query): Query<UrlQuery>) -> StatusCode {
+    let mut registry = state.registry.lock().unwrap();
+    match registry.remove(&query.url) {
+        Some(entry) => {
+            entry.handle.abort();
+            StatusCode::NO_CONTENT
+        }
+        None => StatusCode::NOT_FOUND,
+    }
+}
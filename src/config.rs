@@ -0,0 +1,188 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Instant;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Config {
+    pub sites: Vec<Site>,
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+}
+
+/// Webhook alert targets, notified on up/down/content-changed transitions.
+/// A repeat of the same event for a site within `--notify-cooldown` is not
+/// sent.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct NotificationsConfig {
+    #[serde(default)]
+    pub webhooks: Vec<WebhookTarget>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WebhookTarget {
+    pub url: String,
+    /// If set, requests carry an `X-Signature` header: an HMAC-SHA256 of the
+    /// JSON body, keyed with this secret, so the receiver can verify origin.
+    #[serde(default)]
+    pub hmac_secret: Option<String>,
+}
+
+/// Controls whether rotated log segments are gzip-compressed. Mirrors
+/// `--compress-logs` on the CLI; either one turns compression on.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct CompressionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Site {
+    pub url: String,
+    pub interval: u64, // in seconds
+
+    /// Regex patterns whose matches are stripped from the body before it's
+    /// hashed, so dynamic bits (timestamps, CSRF tokens, ad slots) don't
+    /// trigger spurious "content changed" events. Ignored if `watch_selector`
+    /// is set.
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+
+    /// If set, only the concatenated matches of this pattern are hashed,
+    /// instead of the whole body.
+    #[serde(default)]
+    pub watch_selector: Option<String>,
+
+    /// Per-request timeout. Defaults to 10s if unset.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Extra attempts after a connect/timeout error or 5xx response, before
+    /// giving up and reporting the site down. Defaults to 0 (no retries).
+    #[serde(default)]
+    pub retries: Option<u32>,
+
+    /// Delay between retry attempts. Defaults to 500ms if unset.
+    #[serde(default)]
+    pub retry_backoff_ms: Option<u64>,
+
+    /// Extra headers sent with every request to this site.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// A `Site` with its `ignore_patterns`/`watch_selector` compiled once at
+/// startup (or when POSTed to the control API), so a bad regex is reported
+/// immediately instead of on every check.
+#[derive(Clone)]
+pub struct CompiledSite {
+    pub site: Site,
+    ignore_regexes: Vec<Regex>,
+    watch_regex: Option<Regex>,
+}
+
+impl CompiledSite {
+    pub fn compile(site: Site) -> Result<Self, String> {
+        let ignore_regexes = site
+            .ignore_patterns
+            .iter()
+            .map(|p| {
+                Regex::new(p).map_err(|e| format!("invalid ignore_patterns entry {:?}: {}", p, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let watch_regex = site
+            .watch_selector
+            .as_ref()
+            .map(|p| {
+                Regex::new(p).map_err(|e| format!("invalid watch_selector {:?}: {}", p, e))
+            })
+            .transpose()?;
+
+        Ok(CompiledSite {
+            site,
+            ignore_regexes,
+            watch_regex,
+        })
+    }
+
+    /// Returns the portion of `body` that should actually be hashed for
+    /// change detection: just the `watch_selector` matches if one is set,
+    /// otherwise the whole body with `ignore_patterns` matches removed.
+    pub fn filtered(&self, body: &str) -> String {
+        if let Some(watch) = &self.watch_regex {
+            return watch.find_iter(body).map(|m| m.as_str()).collect();
+        }
+
+        let mut filtered = body.to_string();
+        for pattern in &self.ignore_regexes {
+            filtered = pattern.replace_all(&filtered, "").into_owned();
+        }
+        filtered
+    }
+}
+
+/// One completed check, kept around so `GET /sites/history?url=...` has
+/// something to return beyond the latest snapshot.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckRecord {
+    pub is_up: bool,
+    pub content_hash: Option<String>,
+    pub content_size: Option<usize>,
+    pub load_time_ms: Option<u128>,
+}
+
+/// Live state tracked for a single monitored site, shared between the
+/// `monitor_site` task and the control API.
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteState {
+    pub last_hash: Option<String>,
+    pub last_size: Option<usize>,
+    pub is_up: bool,
+    pub last_load_time: Option<u128>,
+    pub history: Vec<CheckRecord>,
+    /// Last time each event type ("up"/"down"/"content_changed") fired a
+    /// notification, used to debounce flapping sites. Not part of the
+    /// public API response.
+    #[serde(skip)]
+    pub last_notified: HashMap<String, Instant>,
+}
+
+impl SiteState {
+    pub const MAX_HISTORY: usize = 50;
+
+    pub fn new() -> Self {
+        SiteState {
+            last_hash: None,
+            last_size: None,
+            is_up: true,
+            last_load_time: None,
+            history: Vec::new(),
+            last_notified: HashMap::new(),
+        }
+    }
+
+    pub fn push_record(&mut self, record: CheckRecord) {
+        self.history.push(record);
+        if self.history.len() > Self::MAX_HISTORY {
+            self.history.remove(0);
+        }
+    }
+
+    /// Returns whether `event` is outside its cooldown window, and if so
+    /// records now as the last time it fired. Used to stop a flapping site
+    /// from spamming webhook alerts.
+    pub fn should_notify(&mut self, event: &str, cooldown: std::time::Duration) -> bool {
+        let now = Instant::now();
+        let fire = match self.last_notified.get(event) {
+            Some(last) => now.duration_since(*last) >= cooldown,
+            None => true,
+        };
+        if fire {
+            self.last_notified.insert(event.to_string(), now);
+        }
+        fire
+    }
+}
@@ -0,0 +1,196 @@
+use clap::ValueEnum;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Severity of a single log line. Ordered so `--min-level` can filter the
+/// console with a simple comparison; the rotated file always gets
+/// everything regardless of this ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum Severity {
+    Info,
+    Warn,
+    Error,
+    Critical,
+}
+
+impl Severity {
+    fn label(&self) -> &'static str {
+        match self {
+            Severity::Info => "INFO",
+            Severity::Warn => "WARN",
+            Severity::Error => "ERROR",
+            Severity::Critical => "CRITICAL",
+        }
+    }
+
+    // ANSI SGR codes, applied only when stdout is a TTY.
+    fn ansi_prefix(&self) -> &'static str {
+        match self {
+            Severity::Info => "\x1b[32m",      // green
+            Severity::Warn => "\x1b[33m",      // yellow
+            Severity::Error => "\x1b[31m",     // red
+            Severity::Critical => "\x1b[97;41m", // white on red
+        }
+    }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+pub struct Logger {
+    file: Option<Arc<Mutex<File>>>,
+    base_path: Option<PathBuf>,
+    current_lines: Arc<Mutex<usize>>,
+    max_lines: usize,
+    max_files: usize,
+    min_level: Severity,
+    color: bool,
+    compress: bool,
+}
+
+impl Logger {
+    pub fn new(log_file: Option<String>, min_level: Severity, compress: bool) -> Self {
+        let (file, base_path, line_count) = if let Some(path) = log_file {
+            let path_buf = PathBuf::from(&path);
+            match OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path_buf)
+            {
+                Ok(f) => {
+                    // Count existing lines
+                    let count = count_lines(&path_buf).unwrap_or(0);
+                    (Some(Arc::new(Mutex::new(f))), Some(path_buf), count)
+                }
+                Err(e) => {
+                    eprintln!("Failed to open log file {}: {}", path, e);
+                    (None, None, 0)
+                }
+            }
+        } else {
+            (None, None, 0)
+        };
+
+        Logger {
+            file,
+            base_path,
+            current_lines: Arc::new(Mutex::new(line_count)),
+            max_lines: 20_000,
+            max_files: 4,
+            min_level,
+            color: std::io::stdout().is_terminal(),
+            compress,
+        }
+    }
+
+    pub fn log(&self, level: Severity, message: &str) {
+        let plain = format!("[{}] {}", level.label(), message);
+
+        // Console gets color (if a TTY) and respects --min-level.
+        if level >= self.min_level {
+            if self.color {
+                println!("{}{}{}", level.ansi_prefix(), plain, ANSI_RESET);
+            } else {
+                println!("{}", plain);
+            }
+        }
+
+        // The file always gets everything, uncolored.
+        if let Some(file) = &self.file {
+            let mut current_lines = self.current_lines.lock().unwrap();
+
+            if *current_lines >= self.max_lines {
+                drop(current_lines); // Release lock before rotation
+                self.rotate_logs();
+                current_lines = self.current_lines.lock().unwrap();
+            }
+
+            if let Ok(mut f) = file.lock() {
+                if writeln!(f, "{}", plain).is_ok() {
+                    *current_lines += 1;
+                }
+            }
+        }
+    }
+
+    fn rotate_logs(&self) {
+        if let Some(base_path) = &self.base_path {
+            let base_str = base_path.to_string_lossy();
+            let suffix = if self.compress { ".gz" } else { "" };
+
+            // Remove oldest backup if it exists (log.3[.gz])
+            let oldest = format!("{}.{}{}", base_str, self.max_files - 1, suffix);
+            let _ = fs::remove_file(&oldest);
+
+            // Shift existing backups up by one
+            for i in (1..self.max_files - 1).rev() {
+                let from = format!("{}.{}{}", base_str, i, suffix);
+                let to = format!("{}.{}{}", base_str, i + 1, suffix);
+                let _ = fs::rename(&from, &to);
+            }
+
+            // Age the active log out to .1[.gz]
+            let first_backup = format!("{}.1{}", base_str, suffix);
+            if self.compress {
+                if gzip_file(base_path, Path::new(&first_backup)).is_ok() {
+                    let _ = fs::remove_file(base_path);
+                }
+            } else {
+                let _ = fs::rename(base_path, &first_backup);
+            }
+
+            // Create new log file
+            if let Ok(new_file) = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(base_path)
+            {
+                if let Some(file) = &self.file {
+                    if let Ok(mut f) = file.lock() {
+                        *f = new_file;
+                    }
+                }
+                let mut current_lines = self.current_lines.lock().unwrap();
+                *current_lines = 0;
+            }
+        }
+    }
+}
+
+/// Compresses `src` into a new gzip file at `dst`, leaving `src` untouched
+/// (the caller removes it once this succeeds).
+fn gzip_file(src: &Path, dst: &Path) -> std::io::Result<()> {
+    let mut input = File::open(src)?;
+    let mut contents = Vec::new();
+    input.read_to_end(&mut contents)?;
+
+    let out = File::create(dst)?;
+    let mut encoder = GzEncoder::new(out, Compression::default());
+    encoder.write_all(&contents)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+impl Clone for Logger {
+    fn clone(&self) -> Self {
+        Logger {
+            file: self.file.clone(),
+            base_path: self.base_path.clone(),
+            current_lines: self.current_lines.clone(),
+            max_lines: self.max_lines,
+            max_files: self.max_files,
+            min_level: self.min_level,
+            color: self.color,
+            compress: self.compress,
+        }
+    }
+}
+
+fn count_lines(path: &Path) -> std::io::Result<usize> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    Ok(reader.lines().count())
+}
@@ -0,0 +1,189 @@
+use std::time::{Duration, Instant};
+
+use tokio::time;
+
+use crate::config::{CheckRecord, CompiledSite};
+use crate::logger::{Logger, Severity};
+use crate::notify::{NotifyEvent, Notifier};
+use crate::registry::Registry;
+
+const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+const DEFAULT_RETRY_BACKOFF_MS: u64 = 500;
+
+/// Result of a (possibly retried) check of a single site.
+pub struct CheckOutcome {
+    pub is_up: bool,
+    pub hash: String,
+    pub content_size: usize,
+    pub load_time_ms: u128,
+    pub attempts: u32,
+}
+
+/// Checks `site` using the shared `client`, retrying connect/timeout errors
+/// and 5xx responses up to `site.site.retries` extra times with a fixed
+/// backoff between attempts. 4xx responses are treated as a real (non-flaky)
+/// result and returned immediately.
+pub async fn check_site(client: &reqwest::Client, site: &CompiledSite) -> Result<CheckOutcome, String> {
+    let max_attempts = site.site.retries.unwrap_or(0) + 1;
+    let timeout = Duration::from_millis(site.site.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS));
+    let backoff = Duration::from_millis(
+        site.site
+            .retry_backoff_ms
+            .unwrap_or(DEFAULT_RETRY_BACKOFF_MS),
+    );
+
+    let mut last_err = String::new();
+
+    for attempt in 1..=max_attempts {
+        let start = Instant::now();
+        let mut request = client.get(&site.site.url).timeout(timeout);
+        for (key, value) in &site.site.headers {
+            request = request.header(key, value);
+        }
+
+        match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_server_error() && attempt < max_attempts {
+                    last_err = format!("server error: {}", status);
+                    time::sleep(backoff).await;
+                    continue;
+                }
+
+                let is_up = status.is_success();
+                return match response.text().await {
+                    Ok(body) => {
+                        let load_time_ms = start.elapsed().as_millis();
+                        let content_size = body.len();
+                        let filtered = site.filtered(&body);
+                        let hash = format!("{:x}", md5::compute(filtered.as_bytes()));
+                        Ok(CheckOutcome {
+                            is_up,
+                            hash,
+                            content_size,
+                            load_time_ms,
+                            attempts: attempt,
+                        })
+                    }
+                    Err(e) => Err(format!("Failed to read response body: {}", e)),
+                };
+            }
+            Err(e) => {
+                last_err = format!("Request failed: {}", e);
+                if (e.is_timeout() || e.is_connect()) && attempt < max_attempts {
+                    time::sleep(backoff).await;
+                    continue;
+                }
+                return Err(last_err);
+            }
+        }
+    }
+
+    Err(last_err)
+}
+
+/// Runs the check loop for a single site, writing its results back into the
+/// shared `registry` on every tick so the control API always sees live
+/// state. Cancelling this task (e.g. via `DELETE /sites?url=...`) is done by
+/// aborting the `JoinHandle` stored alongside its `SiteEntry`.
+pub async fn monitor_site(
+    site: CompiledSite,
+    registry: Registry,
+    logger: Logger,
+    client: reqwest::Client,
+    notifier: Notifier,
+    notify_cooldown: Duration,
+) {
+    let interval = Duration::from_secs(site.site.interval);
+    let mut interval_timer = time::interval(interval);
+
+    loop {
+        interval_timer.tick().await;
+
+        match check_site(&client, &site).await {
+            Ok(outcome) => {
+                let status = if outcome.is_up { "up" } else { "down" };
+                let hash_short = &outcome.hash[..5.min(outcome.hash.len())];
+
+                let main_msg = format!(
+                    "website: {} | load_time: {}ms | status: {} | size: {}bytes | content_hash: {} | attempts: {}",
+                    site.site.url, outcome.load_time_ms, status, outcome.content_size, hash_short, outcome.attempts
+                );
+                let main_level = if outcome.is_up { Severity::Info } else { Severity::Error };
+                logger.log(main_level, &main_msg);
+
+                let mut registry = registry.lock().unwrap();
+                if let Some(entry) = registry.get_mut(&site.site.url) {
+                    if entry.state.is_up != outcome.is_up {
+                        if outcome.is_up {
+                            logger.log(Severity::Info, "  status changed: down -> up");
+                        } else {
+                            logger.log(Severity::Error, "  status changed: up -> down");
+                        }
+                        entry.state.is_up = outcome.is_up;
+
+                        let event = if outcome.is_up { NotifyEvent::Up } else { NotifyEvent::Down };
+                        if entry.state.should_notify(event.as_str(), notify_cooldown) {
+                            notifier.notify(
+                                &site.site.url,
+                                event,
+                                Some(outcome.load_time_ms),
+                                Some(outcome.content_size),
+                            );
+                        }
+                    }
+
+                    if let Some(last_hash) = &entry.state.last_hash {
+                        if last_hash != &outcome.hash {
+                            logger.log(Severity::Warn, "  content changed");
+                            if entry
+                                .state
+                                .should_notify(NotifyEvent::ContentChanged.as_str(), notify_cooldown)
+                            {
+                                notifier.notify(
+                                    &site.site.url,
+                                    NotifyEvent::ContentChanged,
+                                    Some(outcome.load_time_ms),
+                                    Some(outcome.content_size),
+                                );
+                            }
+                        }
+                    }
+
+                    entry.state.last_hash = Some(outcome.hash.clone());
+                    entry.state.last_size = Some(outcome.content_size);
+                    entry.state.last_load_time = Some(outcome.load_time_ms);
+                    entry.state.push_record(CheckRecord {
+                        is_up: outcome.is_up,
+                        content_hash: Some(outcome.hash),
+                        content_size: Some(outcome.content_size),
+                        load_time_ms: Some(outcome.load_time_ms),
+                    });
+                }
+            }
+            Err(e) => {
+                let error_msg = format!("website: {} | load_time: n/a | status: error", site.site.url);
+                logger.log(Severity::Error, &error_msg);
+                logger.log(Severity::Error, &format!("  error: {}", e));
+
+                let mut registry = registry.lock().unwrap();
+                if let Some(entry) = registry.get_mut(&site.site.url) {
+                    if entry.state.is_up {
+                        logger.log(Severity::Error, "  status changed: up -> down");
+                        entry.state.is_up = false;
+
+                        if entry.state.should_notify(NotifyEvent::Down.as_str(), notify_cooldown) {
+                            notifier.notify(&site.site.url, NotifyEvent::Down, None, None);
+                        }
+                    }
+                    entry.state.push_record(CheckRecord {
+                        is_up: false,
+                        content_hash: None,
+                        content_size: None,
+                        load_time_ms: None,
+                    });
+                }
+            }
+        }
+    }
+}
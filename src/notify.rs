@@ -0,0 +1,166 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::config::WebhookTarget;
+use crate::logger::{Logger, Severity};
+
+const MAX_ATTEMPTS: u32 = 5;
+const RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, Debug)]
+pub enum NotifyEvent {
+    Up,
+    Down,
+    ContentChanged,
+}
+
+impl NotifyEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotifyEvent::Up => "up",
+            NotifyEvent::Down => "down",
+            NotifyEvent::ContentChanged => "content_changed",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Payload {
+    url: String,
+    event: String,
+    timestamp: u64,
+    load_time_ms: Option<u128>,
+    content_size: Option<usize>,
+}
+
+/// Delivers webhook alerts for site transitions. Cheap to clone (an `Arc`'d
+/// client, a `Vec` of targets and a `Logger`) so it can be handed to every
+/// `monitor_site` task alongside the logger.
+#[derive(Clone)]
+pub struct Notifier {
+    client: reqwest::Client,
+    webhooks: Vec<WebhookTarget>,
+    logger: Logger,
+}
+
+impl Notifier {
+    pub fn new(client: reqwest::Client, webhooks: Vec<WebhookTarget>, logger: Logger) -> Self {
+        Notifier {
+            client,
+            webhooks,
+            logger,
+        }
+    }
+
+    /// Fires `event` for `site_url` at every configured webhook target. Each
+    /// delivery runs on its own spawned task with a bounded retry loop, so a
+    /// slow or failing webhook never blocks the monitor loop and a transient
+    /// failure doesn't drop the alert.
+    pub fn notify(
+        &self,
+        site_url: &str,
+        event: NotifyEvent,
+        load_time_ms: Option<u128>,
+        content_size: Option<usize>,
+    ) {
+        if self.webhooks.is_empty() {
+            return;
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let payload = Payload {
+            url: site_url.to_string(),
+            event: event.as_str().to_string(),
+            timestamp,
+            load_time_ms,
+            content_size,
+        };
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                self.logger
+                    .log(Severity::Error, &format!("failed to encode webhook payload: {}", e));
+                return;
+            }
+        };
+
+        for webhook in self.webhooks.clone() {
+            let client = self.client.clone();
+            let logger = self.logger.clone();
+            let body = body.clone();
+            tokio::spawn(async move {
+                deliver_with_retry(&client, &webhook, &body, &logger).await;
+            });
+        }
+    }
+}
+
+async fn deliver_with_retry(
+    client: &reqwest::Client,
+    webhook: &WebhookTarget,
+    body: &[u8],
+    logger: &Logger,
+) {
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json");
+        if let Some(secret) = &webhook.hmac_secret {
+            request = request.header("X-Signature", hmac_hex(secret, body));
+        }
+
+        match request.body(body.to_vec()).send().await {
+            Ok(response) if response.status().is_success() => return,
+            Ok(response) => logger.log(
+                Severity::Warn,
+                &format!(
+                    "webhook {} responded {} (attempt {}/{})",
+                    webhook.url,
+                    response.status(),
+                    attempt,
+                    MAX_ATTEMPTS
+                ),
+            ),
+            Err(e) => logger.log(
+                Severity::Warn,
+                &format!(
+                    "webhook {} delivery failed: {} (attempt {}/{})",
+                    webhook.url, e, attempt, MAX_ATTEMPTS
+                ),
+            ),
+        }
+
+        if attempt < MAX_ATTEMPTS {
+            tokio::time::sleep(RETRY_BACKOFF).await;
+        }
+    }
+
+    logger.log(
+        Severity::Error,
+        &format!(
+            "webhook {} dropped alert after {} attempts",
+            webhook.url, MAX_ATTEMPTS
+        ),
+    );
+}
+
+/// HMAC-SHA256 of `body` keyed with `secret`, hex-encoded.
+fn hmac_hex(secret: &str, body: &[u8]) -> String {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts a key of any length");
+    mac.update(body);
+
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::task::JoinHandle;
+
+use crate::config::{CompiledSite, SiteState};
+
+/// A monitored site together with its live state and the handle of the
+/// `monitor_site` task driving it. Kept together so the API can spawn and
+/// cancel sites at runtime without losing track of either half.
+pub struct SiteEntry {
+    pub site: CompiledSite,
+    pub state: SiteState,
+    pub handle: JoinHandle<()>,
+}
+
+/// Shared table of all monitored sites, keyed by URL. `monitor_site` tasks
+/// write their own `SiteState` back into this map on every check; the API
+/// handlers only ever read or splice entries, they never run checks
+/// themselves.
+pub type Registry = Arc<Mutex<HashMap<String, SiteEntry>>>;
+
+pub fn new_registry() -> Registry {
+    Arc::new(Mutex::new(HashMap::new()))
+}